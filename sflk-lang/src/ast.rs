@@ -1,3 +1,4 @@
+use crate::bigint::BigInt;
 use crate::object::Obj;
 use crate::parser2::ParsingWarning;
 use crate::program;
@@ -32,10 +33,16 @@ impl<T> Node<T> {
 	}
 }
 
+/// A `#...#` comment body together with the `Loc` it was read from.
+pub struct Comment {
+	pub text: String,
+	pub loc: Loc,
+}
+
 struct Comments {
-	left_comments: Vec<String>,
-	right_comments: Vec<String>,
-	internal_comments: Vec<String>,
+	left_comments: Vec<Comment>,
+	right_comments: Vec<Comment>,
+	internal_comments: Vec<Comment>,
 }
 
 impl Comments {
@@ -53,6 +60,22 @@ impl<T> Node<T> {
 		&self.loc
 	}
 
+	pub fn content(&self) -> &T {
+		&self.content
+	}
+
+	pub fn push_left_comment(&mut self, comment: Comment) {
+		self.comments.left_comments.push(comment);
+	}
+
+	pub fn push_right_comment(&mut self, comment: Comment) {
+		self.comments.right_comments.push(comment);
+	}
+
+	pub fn push_internal_comment(&mut self, comment: Comment) {
+		self.comments.internal_comments.push(comment);
+	}
+
 	pub fn add_loc(mut self, loc: Loc) -> Node<T> {
 		// TODO:
 		// Change the + impl for Loc so that this looks better
@@ -116,6 +139,7 @@ pub enum TargetExpr {
 pub enum Expr {
 	VariableName(String),
 	IntegerLiteral(String),
+	FloatLiteral(String),
 	StringLiteral(String),
 	BlockLiteral(Vec<Node<Stmt>>),
 	Chain {
@@ -189,6 +213,9 @@ impl Treeable for Expr {
 			Expr::IntegerLiteral(integer) => {
 				StringTree::new_leaf(format!("integer {}", integer), styles::NORMAL)
 			}
+			Expr::FloatLiteral(float) => {
+				StringTree::new_leaf(format!("float {}", float), styles::NORMAL)
+			}
 			Expr::StringLiteral(string) => StringTree::new_leaf(
 				format!("string \"{}\"", escape_string(string, &styles::UNDERLINE)),
 				styles::NORMAL,
@@ -403,6 +430,7 @@ impl Expr {
 		match self {
 			Expr::VariableName(varname) => false,
 			Expr::IntegerLiteral(integer_string) => false,
+			Expr::FloatLiteral(float_string) => false,
 			Expr::StringLiteral(string_string) => false,
 			Expr::BlockLiteral(stmts) => false,
 			Expr::Chain { init, chops } => {
@@ -419,7 +447,10 @@ impl Expr {
 				varname: varname.to_string(),
 			},
 			Expr::IntegerLiteral(integer_string) => program::Expr::Const {
-				val: Obj::Integer(str::parse(&integer_string).expect("TODO: bigints")),
+				val: Obj::Integer(BigInt::from_digits_str(&integer_string)),
+			},
+			Expr::FloatLiteral(float_string) => program::Expr::Const {
+				val: Obj::Float(str::parse(&float_string).expect("lexer only yields valid floats")),
 			},
 			Expr::StringLiteral(string_string) => program::Expr::Const {
 				val: Obj::String(string_string.clone()),
@@ -444,6 +475,180 @@ impl Expr {
 	}
 }
 
+pub trait SourceWriteable {
+	fn write_source(&self, out: &mut String);
+}
+
+impl<T> Node<T>
+where
+	T: SourceWriteable,
+{
+	fn write_source(&self, out: &mut String) {
+		for comment in &self.comments.left_comments {
+			out.push('#');
+			out.push_str(&comment.text);
+			out.push_str("# ");
+		}
+		for comment in &self.comments.internal_comments {
+			out.push('#');
+			out.push_str(&comment.text);
+			out.push_str("# ");
+		}
+		self.content.write_source(out);
+		for comment in &self.comments.right_comments {
+			out.push_str(" #");
+			out.push_str(&comment.text);
+			out.push('#');
+		}
+	}
+}
+
+impl SourceWriteable for TargetExpr {
+	fn write_source(&self, out: &mut String) {
+		match self {
+			TargetExpr::VariableName(name) => out.push_str(name),
+			TargetExpr::Invalid => out.push_str("<invalid>"),
+		}
+	}
+}
+
+impl SourceWriteable for Expr {
+	fn write_source(&self, out: &mut String) {
+		match self {
+			Expr::VariableName(name) => out.push_str(name),
+			Expr::IntegerLiteral(integer) => out.push_str(integer),
+			Expr::FloatLiteral(float) => out.push_str(float),
+			Expr::StringLiteral(string) => {
+				out.push('"');
+				out.push_str(string);
+				out.push('"');
+			}
+			Expr::BlockLiteral(stmts) => {
+				out.push('{');
+				for stmt_node in stmts {
+					stmt_node.write_source(out);
+					out.push_str("; ");
+				}
+				out.push('}');
+			}
+			Expr::Chain { init, chops } => {
+				init.write_source(out);
+				for chop_node in chops {
+					out.push(' ');
+					chop_node.write_source(out);
+				}
+			}
+			Expr::Invalid => out.push_str("<invalid>"),
+		}
+	}
+}
+
+/// Writes a chop's operand, parenthesized when it is itself a chain so that
+/// it re-parses as a single operand.
+fn write_chop_operand(expr: &Node<Expr>, out: &mut String) {
+	if let Expr::Chain { .. } = expr.content() {
+		out.push('(');
+		expr.write_source(out);
+		out.push(')');
+	} else {
+		expr.write_source(out);
+	}
+}
+
+impl SourceWriteable for Chop {
+	fn write_source(&self, out: &mut String) {
+		match self {
+			Chop::Plus(expr) => {
+				out.push_str("+ ");
+				write_chop_operand(expr, out);
+			}
+			Chop::Minus(expr) => {
+				out.push_str("- ");
+				write_chop_operand(expr, out);
+			}
+			Chop::Star(expr) => {
+				out.push_str("* ");
+				write_chop_operand(expr, out);
+			}
+			Chop::Slash(expr) => {
+				out.push_str("/ ");
+				write_chop_operand(expr, out);
+			}
+			Chop::ToRight(expr) => {
+				out.push_str("> ");
+				write_chop_operand(expr, out);
+			}
+			Chop::Invalid => out.push_str("<invalid>"),
+		}
+	}
+}
+
+impl SourceWriteable for Stmt {
+	fn write_source(&self, out: &mut String) {
+		match self {
+			Stmt::Nop => out.push_str("nop"),
+			Stmt::Print { expr } => {
+				out.push_str("pr ");
+				expr.write_source(out);
+			}
+			Stmt::Newline => out.push_str("nl"),
+			Stmt::Assign { target, expr } => {
+				target.write_source(out);
+				out.push_str(" <- ");
+				expr.write_source(out);
+			}
+			Stmt::Evaluate { expr } => {
+				out.push_str("ev ");
+				expr.write_source(out);
+			}
+			Stmt::Do { expr } => {
+				out.push_str("do ");
+				expr.write_source(out);
+			}
+			Stmt::DoHere { expr } => {
+				out.push_str("dh ");
+				expr.write_source(out);
+			}
+			Stmt::DoFileHere { expr } => {
+				out.push_str("dfh ");
+				expr.write_source(out);
+			}
+			Stmt::If {
+				cond_expr,
+				th_stmt,
+				el_stmt,
+			} => {
+				out.push_str("if ");
+				cond_expr.write_source(out);
+				if let Some(stmt) = th_stmt {
+					out.push_str(" th ");
+					stmt.write_source(out);
+				}
+				if let Some(stmt) = el_stmt {
+					out.push_str(" el ");
+					stmt.write_source(out);
+				}
+			}
+			Stmt::Invalid => out.push_str("<invalid>"),
+		}
+	}
+}
+
+impl Program {
+	/// Re-emits canonical SFLK source text for this tree, with left comments
+	/// written before their node and right comments after it. Internal
+	/// comments (such as those of an empty block) are written before the
+	/// node's content, after its left comments, not inside the node.
+	pub fn to_source_string(&self) -> String {
+		let mut out = String::new();
+		for stmt_node in &self.stmts {
+			stmt_node.write_source(&mut out);
+			out.push('\n');
+		}
+		out
+	}
+}
+
 impl Chop {
 	fn is_invalid(&self) -> bool {
 		match self {
@@ -0,0 +1,313 @@
+// Minimal arbitrary-precision integer, sign-and-magnitude over base-1e9 limbs
+// (little-endian), just big enough to back `Obj::Integer` and the
+// `Chop::Plus`/`Minus`/`Star`/`Slash` arithmetic done on it.
+
+const LIMB_BASE: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+	negative: bool,
+	// Little-endian base-1e9 limbs, no trailing zero limbs (except `[0]` for zero).
+	limbs: Vec<u32>,
+}
+
+impl BigInt {
+	pub fn zero() -> BigInt {
+		BigInt {
+			negative: false,
+			limbs: vec![0],
+		}
+	}
+
+	/// Parses the integer-literal string the lexer's `read_cur_number` yields,
+	/// `0x`/`0o`/`0b`-prefixed or plain decimal. Always succeeds: any digit
+	/// run the lexer can produce for that radix fits, however long.
+	pub fn from_digits_str(digits: &str) -> BigInt {
+		let bytes = digits.as_bytes();
+		if bytes.len() >= 2 && bytes[0] == b'0' {
+			match bytes[1] {
+				b'x' | b'X' => return BigInt::from_radix_digits_str(&digits[2..], 16),
+				b'o' | b'O' => return BigInt::from_radix_digits_str(&digits[2..], 8),
+				b'b' | b'B' => return BigInt::from_radix_digits_str(&digits[2..], 2),
+				_ => (),
+			}
+		}
+		BigInt::from_decimal_digits_str(digits)
+	}
+
+	/// Parses a run of plain decimal ASCII digits, chunking it into base-1e9
+	/// limbs directly instead of accumulating digit-by-digit.
+	fn from_decimal_digits_str(digits: &str) -> BigInt {
+		let mut limbs: Vec<u32> = Vec::new();
+		let bytes = digits.as_bytes();
+		let mut end = bytes.len();
+		while end > 0 {
+			let start = end.saturating_sub(9);
+			let chunk = std::str::from_utf8(&bytes[start..end]).unwrap();
+			limbs.push(chunk.parse().unwrap());
+			end = start;
+		}
+		if limbs.is_empty() {
+			limbs.push(0);
+		}
+		let mut big = BigInt {
+			negative: false,
+			limbs,
+		};
+		big.trim();
+		big
+	}
+
+	/// Parses a run of `radix`-digits (`radix` other than 10) via Horner's
+	/// method: `big = big * radix + digit` for each digit, most significant
+	/// first.
+	fn from_radix_digits_str(digits: &str, radix: u32) -> BigInt {
+		let radix_big = BigInt::from_decimal_digits_str(&radix.to_string());
+		let mut big = BigInt::zero();
+		for ch in digits.chars() {
+			let digit = ch.to_digit(radix).expect("lexer only yields valid radix digits");
+			big = &(&big * &radix_big) + &BigInt::from_decimal_digits_str(&digit.to_string());
+		}
+		big
+	}
+
+	fn trim(&mut self) {
+		while self.limbs.len() > 1 && *self.limbs.last().unwrap() == 0 {
+			self.limbs.pop();
+		}
+		if self.limbs == [0] {
+			self.negative = false;
+		}
+	}
+
+	fn is_zero(&self) -> bool {
+		self.limbs == [0]
+	}
+
+	fn magnitude_cmp(a: &[u32], b: &[u32]) -> std::cmp::Ordering {
+		if a.len() != b.len() {
+			return a.len().cmp(&b.len());
+		}
+		for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+			if x != y {
+				return x.cmp(y);
+			}
+		}
+		std::cmp::Ordering::Equal
+	}
+
+	fn magnitude_add(a: &[u32], b: &[u32]) -> Vec<u32> {
+		let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+		let mut carry: u64 = 0;
+		for i in 0..a.len().max(b.len()) {
+			let x = *a.get(i).unwrap_or(&0) as u64;
+			let y = *b.get(i).unwrap_or(&0) as u64;
+			let sum = x + y + carry;
+			result.push((sum % LIMB_BASE) as u32);
+			carry = sum / LIMB_BASE;
+		}
+		if carry > 0 {
+			result.push(carry as u32);
+		}
+		result
+	}
+
+	// Assumes `a >= b` in magnitude.
+	fn magnitude_sub(a: &[u32], b: &[u32]) -> Vec<u32> {
+		let mut result = Vec::with_capacity(a.len());
+		let mut borrow: i64 = 0;
+		for (i, &x) in a.iter().enumerate() {
+			let x = x as i64;
+			let y = *b.get(i).unwrap_or(&0) as i64;
+			let mut diff = x - y - borrow;
+			if diff < 0 {
+				diff += LIMB_BASE as i64;
+				borrow = 1;
+			} else {
+				borrow = 0;
+			}
+			result.push(diff as u32);
+		}
+		result
+	}
+
+	fn magnitude_mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+		let mut result = vec![0u64; a.len() + b.len()];
+		for (i, &x) in a.iter().enumerate() {
+			let mut carry: u64 = 0;
+			for (j, &y) in b.iter().enumerate() {
+				let product = result[i + j] + x as u64 * y as u64 + carry;
+				result[i + j] = product % LIMB_BASE;
+				carry = product / LIMB_BASE;
+			}
+			let mut k = i + b.len();
+			while carry > 0 {
+				let sum = result[k] + carry;
+				result[k] = sum % LIMB_BASE;
+				carry = sum / LIMB_BASE;
+				k += 1;
+			}
+		}
+		let mut result: Vec<u32> = result.into_iter().map(|limb| limb as u32).collect();
+		// `a.len() + b.len()` over-allocates whenever the product doesn't need
+		// every limb; trim so callers comparing magnitudes by limb count (like
+		// `magnitude_div_rem`'s binary search) see the product's true length.
+		while result.len() > 1 && *result.last().unwrap() == 0 {
+			result.pop();
+		}
+		result
+	}
+
+	// Schoolbook long division, one base-1e9 limb at a time. Assumes `b` is
+	// non-zero. Returns (quotient, remainder) magnitudes.
+	fn magnitude_div_rem(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+		let mut quotient = vec![0u32; a.len()];
+		let mut remainder: Vec<u32> = vec![0];
+		for i in (0..a.len()).rev() {
+			remainder.insert(0, a[i]);
+			while remainder.len() > 1 && *remainder.last().unwrap() == 0 {
+				remainder.pop();
+			}
+			let mut lo: u64 = 0;
+			let mut hi: u64 = LIMB_BASE - 1;
+			while lo < hi {
+				let mid = (lo + hi).div_ceil(2);
+				let candidate = BigInt::magnitude_mul(b, &[mid as u32]);
+				if BigInt::magnitude_cmp(&candidate, &remainder) != std::cmp::Ordering::Greater {
+					lo = mid;
+				} else {
+					hi = mid - 1;
+				}
+			}
+			quotient[i] = lo as u32;
+			remainder = BigInt::magnitude_sub(&remainder, &BigInt::magnitude_mul(b, &[lo as u32]));
+			while remainder.len() > 1 && *remainder.last().unwrap() == 0 {
+				remainder.pop();
+			}
+		}
+		(quotient, remainder)
+	}
+}
+
+impl std::ops::Add for &BigInt {
+	type Output = BigInt;
+
+	fn add(self, rhs: &BigInt) -> BigInt {
+		let mut result = if self.negative == rhs.negative {
+			BigInt {
+				negative: self.negative,
+				limbs: BigInt::magnitude_add(&self.limbs, &rhs.limbs),
+			}
+		} else if BigInt::magnitude_cmp(&self.limbs, &rhs.limbs) != std::cmp::Ordering::Less {
+			BigInt {
+				negative: self.negative,
+				limbs: BigInt::magnitude_sub(&self.limbs, &rhs.limbs),
+			}
+		} else {
+			BigInt {
+				negative: rhs.negative,
+				limbs: BigInt::magnitude_sub(&rhs.limbs, &self.limbs),
+			}
+		};
+		result.trim();
+		result
+	}
+}
+
+impl std::ops::Sub for &BigInt {
+	type Output = BigInt;
+
+	#[allow(clippy::suspicious_arithmetic_impl)] // a - b is implemented as a + (-b)
+	fn sub(self, rhs: &BigInt) -> BigInt {
+		self + &rhs.negated()
+	}
+}
+
+impl std::ops::Mul for &BigInt {
+	type Output = BigInt;
+
+	fn mul(self, rhs: &BigInt) -> BigInt {
+		let mut result = BigInt {
+			negative: self.negative != rhs.negative,
+			limbs: BigInt::magnitude_mul(&self.limbs, &rhs.limbs),
+		};
+		result.trim();
+		result
+	}
+}
+
+impl std::ops::Div for &BigInt {
+	type Output = BigInt;
+
+	/// Truncating division, panics on division by zero (same contract as
+	/// the native integer division it replaces).
+	fn div(self, rhs: &BigInt) -> BigInt {
+		if rhs.is_zero() {
+			panic!("division by zero");
+		}
+		let (quotient, _) = BigInt::magnitude_div_rem(&self.limbs, &rhs.limbs);
+		let mut result = BigInt {
+			negative: self.negative != rhs.negative,
+			limbs: quotient,
+		};
+		result.trim();
+		result
+	}
+}
+
+impl BigInt {
+	fn negated(&self) -> BigInt {
+		let mut negated = self.clone();
+		if !negated.is_zero() {
+			negated.negative = !negated.negative;
+		}
+		negated
+	}
+}
+
+impl std::fmt::Display for BigInt {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		if self.negative {
+			write!(f, "-")?;
+		}
+		let mut limbs = self.limbs.iter().rev();
+		write!(f, "{}", limbs.next().unwrap())?;
+		for limb in limbs {
+			write!(f, "{:09}", limb)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::BigInt;
+
+	#[test]
+	fn decimal_digits_round_trip() {
+		assert_eq!(BigInt::from_digits_str("0").to_string(), "0");
+		assert_eq!(BigInt::from_digits_str("255").to_string(), "255");
+		assert_eq!(
+			BigInt::from_digits_str("123456789123456789").to_string(),
+			"123456789123456789"
+		);
+	}
+
+	#[test]
+	fn radix_prefixed_digits_dont_panic() {
+		assert_eq!(BigInt::from_digits_str("0xFF").to_string(), "255");
+		assert_eq!(BigInt::from_digits_str("0o17").to_string(), "15");
+		assert_eq!(BigInt::from_digits_str("0b1010").to_string(), "10");
+		assert_eq!(BigInt::from_digits_str("0x0").to_string(), "0");
+	}
+
+	#[test]
+	fn arithmetic_matches_native_integers() {
+		let a = BigInt::from_digits_str("1000000000000");
+		let b = BigInt::from_digits_str("999999999999");
+		assert_eq!((&a - &b).to_string(), "1");
+		assert_eq!((&a + &b).to_string(), "1999999999999");
+		assert_eq!((&a * &BigInt::from_digits_str("2")).to_string(), "2000000000000");
+		assert_eq!((&a / &BigInt::from_digits_str("3")).to_string(), "333333333333");
+	}
+}
@@ -0,0 +1,61 @@
+use crate::bigint::BigInt;
+use crate::program;
+
+/// A runtime SFLK value, as produced by evaluating a `program::Expr`.
+#[derive(Debug)]
+pub enum Obj {
+	Integer(BigInt),
+	Float(f64),
+	String(String),
+	Block(program::Block),
+}
+
+impl std::ops::Add for &Obj {
+	type Output = Obj;
+
+	fn add(self, rhs: &Obj) -> Obj {
+		match (self, rhs) {
+			(Obj::Integer(a), Obj::Integer(b)) => Obj::Integer(a + b),
+			(Obj::Float(a), Obj::Float(b)) => Obj::Float(a + b),
+			_ => panic!("`+` chop requires two integers or two floats"),
+		}
+	}
+}
+
+impl std::ops::Sub for &Obj {
+	type Output = Obj;
+
+	fn sub(self, rhs: &Obj) -> Obj {
+		match (self, rhs) {
+			(Obj::Integer(a), Obj::Integer(b)) => Obj::Integer(a - b),
+			(Obj::Float(a), Obj::Float(b)) => Obj::Float(a - b),
+			_ => panic!("`-` chop requires two integers or two floats"),
+		}
+	}
+}
+
+impl std::ops::Mul for &Obj {
+	type Output = Obj;
+
+	fn mul(self, rhs: &Obj) -> Obj {
+		match (self, rhs) {
+			(Obj::Integer(a), Obj::Integer(b)) => Obj::Integer(a * b),
+			(Obj::Float(a), Obj::Float(b)) => Obj::Float(a * b),
+			_ => panic!("`*` chop requires two integers or two floats"),
+		}
+	}
+}
+
+impl std::ops::Div for &Obj {
+	type Output = Obj;
+
+	/// Integer division by zero panics, same contract as `BigInt`'s own `Div`
+	/// impl; float division by zero does not panic and follows `f64`.
+	fn div(self, rhs: &Obj) -> Obj {
+		match (self, rhs) {
+			(Obj::Integer(a), Obj::Integer(b)) => Obj::Integer(a / b),
+			(Obj::Float(a), Obj::Float(b)) => Obj::Float(a / b),
+			_ => panic!("`/` chop requires two integers or two floats"),
+		}
+	}
+}
@@ -0,0 +1,367 @@
+use crate::ast::{Chop, Comment, Expr, Node, Program, Stmt, TargetExpr};
+use crate::scu::{CommentList, Loc, ParsingError, ReadingHead, SourceCodeUnit, Tok};
+use std::rc::Rc;
+
+/// A non-fatal note attached to a `Node`, for diagnostics softer than a hard
+/// `ParsingError` (e.g. a style suggestion). Nothing produces one yet; the
+/// type exists so `Node` has somewhere to put one once something does.
+pub struct ParsingWarning {
+	pub message: String,
+	pub loc: Loc,
+}
+
+fn is_stmt_keyword(word: &str) -> bool {
+	matches!(
+		word,
+		"nop" | "pr" | "nl" | "ev" | "do" | "dh" | "dfh" | "if" | "th" | "el"
+	)
+}
+
+/// Attaches leading comment trivia to a freshly-built node and returns it.
+fn with_leading_comments<T>(mut node: Node<T>, comments: CommentList) -> Node<T> {
+	for (text, loc, _same_line) in comments {
+		node.push_left_comment(Comment { text, loc });
+	}
+	node
+}
+
+/// Splits a comment list gathered just after a construct into the
+/// construct's own trailing (same-line) comments and the leftover, which
+/// leads whatever is parsed next.
+fn split_trailing(comments: CommentList) -> (CommentList, CommentList) {
+	let split_at = comments
+		.iter()
+		.position(|(_, _, same_line)| !same_line)
+		.unwrap_or(comments.len());
+	let mut comments = comments;
+	let rest = comments.split_off(split_at);
+	(comments, rest)
+}
+
+/// A single-token-lookahead reader over `ReadingHead`, resilient at the
+/// token level (via `read_cur_tok_resilient`) and, on top of that,
+/// resilient at the statement level: a construct that can't be parsed is
+/// recorded as a `ParsingError` and synthesized as a
+/// `Stmt::Invalid`/`Expr::Invalid`, and reading resumes at the next
+/// statement boundary instead of stopping.
+struct Parser {
+	head: ReadingHead,
+	cur: (Tok, Loc, CommentList),
+	errors: Vec<ParsingError>,
+}
+
+impl Parser {
+	fn new(scu: Rc<SourceCodeUnit>) -> Parser {
+		let mut head = ReadingHead::from_scu(scu);
+		let mut errors = Vec::new();
+		let cur = head.read_cur_tok_resilient(&mut errors);
+		Parser { head, cur, errors }
+	}
+
+	fn bump(&mut self) {
+		self.cur = self.head.read_cur_tok_resilient(&mut self.errors);
+	}
+
+	/// Drops whatever's left of the current statement and refills `cur` from
+	/// the next statement boundary, so a synthesized `Invalid` node doesn't
+	/// also poison the statement after it.
+	fn resync(&mut self) {
+		self.head.skip_to_next_stmt_boundary();
+		self.cur = self.head.read_cur_tok_resilient(&mut self.errors);
+	}
+
+	fn unexpected(&mut self, description: String, loc: Loc) {
+		self.errors.push(ParsingError::UnexpectedToken { description, loc });
+	}
+
+	fn cur_word_is(&self, word: &str) -> bool {
+		matches!(&self.cur.0, Tok::Word(w) if w == word)
+	}
+
+	fn cur_is_assign_arrow(&self) -> bool {
+		matches!(&self.cur.0, Tok::BinOp(op) if op == "<-")
+	}
+
+	/// Claims this construct's trailing same-line comments (e.g. the `#done#`
+	/// in `pr 1 #done#`) from the lookahead gathered for whatever comes next,
+	/// putting back whatever's left over so the next construct still sees it
+	/// as its own leading comments.
+	fn attach_trailing_comments<T>(&mut self, node: &mut Node<T>) {
+		let (trailing, rest) = split_trailing(std::mem::take(&mut self.cur.2));
+		for (text, loc, _same_line) in trailing {
+			node.push_right_comment(Comment { text, loc });
+		}
+		self.cur.2 = rest;
+	}
+
+	/// Reads a single atom: a literal, a variable name, a parenthesized
+	/// expression, or a block. Not a `Chain`; `read_expr` builds those out of
+	/// atoms linked by chops.
+	fn read_atom(&mut self) -> Node<Expr> {
+		let comments = std::mem::take(&mut self.cur.2);
+		let loc = self.cur.1.clone();
+		match self.cur.0.clone() {
+			Tok::Integer(s) => {
+				self.bump();
+				with_leading_comments(Node::from(Expr::IntegerLiteral(s), loc), comments)
+			},
+			Tok::Float(s) => {
+				self.bump();
+				with_leading_comments(Node::from(Expr::FloatLiteral(s), loc), comments)
+			},
+			Tok::Word(w) if !is_stmt_keyword(&w) => {
+				self.bump();
+				with_leading_comments(Node::from(Expr::VariableName(w), loc), comments)
+			},
+			Tok::Left(bracket) if bracket == "(" => {
+				self.bump();
+				let inner = self.read_expr();
+				self.expect_right(")");
+				with_leading_comments(inner, comments)
+			},
+			Tok::Left(bracket) if bracket == "{" => {
+				self.bump();
+				// An empty block's comments (e.g. `{ #hi# }`) have no child
+				// node to lead, so they belong to the block itself.
+				let dangling = if matches!(&self.cur.0, Tok::Right(b) if b == "}") {
+					std::mem::take(&mut self.cur.2)
+				} else {
+					CommentList::new()
+				};
+				let stmts = self.read_block_stmts();
+				let mut node = with_leading_comments(Node::from(Expr::BlockLiteral(stmts), loc), comments);
+				for (text, loc, _same_line) in dangling {
+					node.push_internal_comment(Comment { text, loc });
+				}
+				node
+			},
+			other => {
+				self.unexpected(format!("{:?}", other), loc.clone());
+				self.resync();
+				with_leading_comments(Node::from(Expr::Invalid, loc), comments)
+			},
+		}
+	}
+
+	/// Reads an atom followed by zero or more chops (`+`/`-`/`*`/`/`/`>`
+	/// each followed by another atom), building a `Chain` only when there's
+	/// at least one chop.
+	fn read_expr(&mut self) -> Node<Expr> {
+		let init = self.read_atom();
+		let mut chops = Vec::new();
+		loop {
+			let op = match &self.cur.0 {
+				Tok::BinOp(op) if matches!(op.as_str(), "+" | "-" | "*" | "/" | ">") => op.clone(),
+				_ => break,
+			};
+			let comments = std::mem::take(&mut self.cur.2);
+			let loc = self.cur.1.clone();
+			self.bump();
+			let rhs = self.read_atom();
+			let chop = match op.as_str() {
+				"+" => Chop::Plus(rhs),
+				"-" => Chop::Minus(rhs),
+				"*" => Chop::Star(rhs),
+				"/" => Chop::Slash(rhs),
+				">" => Chop::ToRight(rhs),
+				_ => unreachable!(),
+			};
+			chops.push(with_leading_comments(Node::from(chop, loc), comments));
+		}
+		if chops.is_empty() {
+			init
+		} else {
+			let init_loc = init.loc().clone();
+			Node::from(
+				Expr::Chain {
+					init: Box::new(init),
+					chops,
+				},
+				init_loc,
+			)
+		}
+	}
+
+	fn expect_right(&mut self, bracket: &str) {
+		match &self.cur.0 {
+			Tok::Right(b) if b == bracket => self.bump(),
+			_ => {
+				let description = format!("expected closing `{}`, found {:?}", bracket, self.cur.0);
+				let loc = self.cur.1.clone();
+				self.unexpected(description, loc);
+				self.resync();
+			},
+		}
+	}
+
+	/// Reads statements until a closing `}` (consumed) or end of input.
+	fn read_block_stmts(&mut self) -> Vec<Node<Stmt>> {
+		let mut stmts = Vec::new();
+		loop {
+			match &self.cur.0 {
+				Tok::Right(bracket) if bracket == "}" => {
+					self.bump();
+					break;
+				},
+				Tok::Void => break,
+				_ => stmts.push(self.read_stmt()),
+			}
+		}
+		stmts
+	}
+
+	fn read_stmt(&mut self) -> Node<Stmt> {
+		let comments = std::mem::take(&mut self.cur.2);
+		let loc = self.cur.1.clone();
+		let content = match self.cur.0.clone() {
+			Tok::Word(w) if w == "nop" => {
+				self.bump();
+				Stmt::Nop
+			},
+			Tok::Word(w) if w == "nl" => {
+				self.bump();
+				Stmt::Newline
+			},
+			Tok::Word(w) if w == "pr" => {
+				self.bump();
+				let expr = self.read_expr();
+				Stmt::Print { expr }
+			},
+			Tok::Word(w) if w == "ev" => {
+				self.bump();
+				let expr = self.read_expr();
+				Stmt::Evaluate { expr }
+			},
+			Tok::Word(w) if w == "do" => {
+				self.bump();
+				let expr = self.read_expr();
+				Stmt::Do { expr }
+			},
+			Tok::Word(w) if w == "dh" => {
+				self.bump();
+				let expr = self.read_expr();
+				Stmt::DoHere { expr }
+			},
+			Tok::Word(w) if w == "dfh" => {
+				self.bump();
+				let expr = self.read_expr();
+				Stmt::DoFileHere { expr }
+			},
+			Tok::Word(w) if w == "if" => {
+				self.bump();
+				let cond_expr = self.read_expr();
+				let th_stmt = if self.cur_word_is("th") {
+					self.bump();
+					Some(Box::new(self.read_stmt()))
+				} else {
+					None
+				};
+				let el_stmt = if self.cur_word_is("el") {
+					self.bump();
+					Some(Box::new(self.read_stmt()))
+				} else {
+					None
+				};
+				Stmt::If {
+					cond_expr,
+					th_stmt,
+					el_stmt,
+				}
+			},
+			Tok::Word(_) | Tok::Integer(_) | Tok::Float(_) | Tok::Left(_) => {
+				// Could be a bare expression statement, or (if the expression
+				// turns out to be a bare variable name immediately followed
+				// by `<-`) an assignment.
+				let expr = self.read_expr();
+				if let Expr::VariableName(name) = expr.content() {
+					if self.cur_is_assign_arrow() {
+						let target = Node::from(TargetExpr::VariableName(name.clone()), expr.loc().clone());
+						self.bump(); // the `<-`
+						let value_expr = self.read_expr();
+						Stmt::Assign {
+							target,
+							expr: value_expr,
+						}
+					} else {
+						Stmt::Evaluate { expr }
+					}
+				} else {
+					Stmt::Evaluate { expr }
+				}
+			},
+			other => {
+				self.unexpected(format!("{:?}", other), loc.clone());
+				self.resync();
+				Stmt::Invalid
+			},
+		};
+		let mut node = with_leading_comments(Node::from(content, loc), comments);
+		self.attach_trailing_comments(&mut node);
+		node
+	}
+
+	fn read_program(&mut self) -> Program {
+		let mut stmts = Vec::new();
+		while !matches!(self.cur.0, Tok::Void) {
+			stmts.push(self.read_stmt());
+		}
+		Program { stmts }
+	}
+}
+
+/// Parses `scu` resiliently: a syntax error never stops the parse. The
+/// offending statement or expression is recorded as a `ParsingError` and
+/// synthesized as a `Stmt::Invalid`/`Expr::Invalid` node, and reading
+/// resumes at the next statement boundary, so one call reports every
+/// syntax error in the source instead of just the first.
+pub fn parse_resilient(scu: Rc<SourceCodeUnit>) -> (Program, Vec<ParsingError>) {
+	let mut parser = Parser::new(scu);
+	let program = parser.read_program();
+	(program, parser.errors)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::parse_resilient;
+	use crate::scu::SourceCodeUnit;
+	use std::rc::Rc;
+
+	#[test]
+	fn leading_comments_survive_the_round_trip() {
+		let scu = Rc::new(SourceCodeUnit::from_str("#greet# pr 1\n", "test".to_string()));
+		let (program, errors) = parse_resilient(scu);
+		assert!(errors.is_empty());
+		assert_eq!(program.to_source_string(), "#greet# pr 1\n");
+	}
+
+	#[test]
+	fn unparseable_statements_become_invalid_and_parsing_keeps_going() {
+		let scu = Rc::new(SourceCodeUnit::from_str("}\npr 1\n", "test".to_string()));
+		let (program, errors) = parse_resilient(scu);
+		assert_eq!(program.stmts.len(), 2);
+		assert_eq!(errors.len(), 1);
+		assert_eq!(program.to_source_string(), "<invalid>\npr 1\n");
+	}
+
+	#[test]
+	fn parses_assignment_and_if_then_else() {
+		let scu = Rc::new(SourceCodeUnit::from_str("x <- 1\nif x th pr 1 el pr 2\n", "test".to_string()));
+		let (program, errors) = parse_resilient(scu);
+		assert!(errors.is_empty());
+		assert_eq!(
+			program.to_source_string(),
+			"x <- 1\nif x th pr 1 el pr 2\n"
+		);
+	}
+
+	#[test]
+	fn trailing_same_line_comment_attaches_to_the_statement_it_follows() {
+		// If this comment were (mis)attached as the next statement's leading
+		// comment instead, it would round-trip as "pr 1\n#done# pr 2\n".
+		let scu = Rc::new(SourceCodeUnit::from_str("pr 1 #done#\npr 2\n", "test".to_string()));
+		let (program, errors) = parse_resilient(scu);
+		assert!(errors.is_empty());
+		assert_eq!(program.stmts.len(), 2);
+		assert_eq!(program.to_source_string(), "pr 1 #done#\npr 2\n");
+	}
+}
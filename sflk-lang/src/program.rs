@@ -0,0 +1,60 @@
+use crate::object::Obj;
+
+/// The flat, trivia-free tree `ast::Program::to_machine_block` lowers to,
+/// ready for evaluation.
+#[derive(Debug)]
+pub struct Block {
+	pub stmts: Vec<Stmt>,
+}
+
+#[derive(Debug)]
+pub enum Stmt {
+	Nop,
+	Print { expr: Expr },
+	Newline,
+	Assign { varname: String, expr: Expr },
+	Evaluate { expr: Expr },
+	Do { expr: Expr },
+	DoHere { expr: Expr },
+	DoFileHere { expr: Expr },
+	If {
+		cond_expr: Expr,
+		th_stmt: Option<Box<Stmt>>,
+		el_stmt: Option<Box<Stmt>>,
+	},
+	Invalid,
+}
+
+#[derive(Debug)]
+pub enum Expr {
+	Var { varname: String },
+	Const { val: Obj },
+	Chain { init_expr: Box<Expr>, chops: Vec<Chop> },
+}
+
+#[derive(Debug)]
+pub enum Chop {
+	Plus(Expr),
+	Minus(Expr),
+	Star(Expr),
+	Slash(Expr),
+	ToRight(Expr),
+}
+
+impl Chop {
+	/// Applies this chop to a chain's running accumulator, evaluating this
+	/// chop's own `Expr` via `eval_expr` (supplied by the surrounding
+	/// evaluator, which owns the variable environment `Expr::Var` needs).
+	/// `Plus`/`Minus`/`Star`/`Slash` defer to `Obj`'s arithmetic impls;
+	/// `ToRight` isn't arithmetic and just replaces the accumulator with its
+	/// right-hand side.
+	pub fn apply(&self, acc: Obj, eval_expr: impl FnOnce(&Expr) -> Obj) -> Obj {
+		match self {
+			Chop::Plus(expr) => &acc + &eval_expr(expr),
+			Chop::Minus(expr) => &acc - &eval_expr(expr),
+			Chop::Star(expr) => &acc * &eval_expr(expr),
+			Chop::Slash(expr) => &acc / &eval_expr(expr),
+			Chop::ToRight(expr) => eval_expr(expr),
+		}
+	}
+}
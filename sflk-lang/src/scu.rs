@@ -0,0 +1,509 @@
+use crate::utils::styles;
+
+#[derive(Debug)]
+pub struct SourceCodeUnit {
+	name: String,
+	content: String,
+	line_offsets: Vec<usize>,
+}
+
+impl SourceCodeUnit {
+	pub fn from_filename(filename: &str) -> SourceCodeUnit {
+		let src = std::fs::read_to_string(filename)
+			.expect(&format!("source file `{}` couldn't be read", filename));
+		SourceCodeUnit::from_str(&src, filename.to_string())
+	}
+
+	pub fn from_str(s: &str, name: String) -> SourceCodeUnit {
+		let line_offsets_iter = s.bytes()
+			.enumerate()
+			.filter_map(|(i, ch)|
+				if ch as char == '\n' {
+					Some(i+1)
+				} else {
+					None 
+				});
+		let mut line_offsets: Vec<usize> = Some(0usize).into_iter()
+			.chain(line_offsets_iter)
+			.collect();
+		let mut content = s.to_string();
+		if *line_offsets.last().unwrap() != content.len() {
+			content += "\n";
+			line_offsets.push(content.len());
+			// If the content didn't end by a `\n`, then now it does.
+		}
+		SourceCodeUnit {
+			name: name,
+			content: content,
+			line_offsets: line_offsets,
+		}
+	}
+}
+
+
+/// A zero-based line number paired with a one-based, char-counted column,
+/// resolved from a raw byte offset via `SourceCodeUnit::line_col`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+	pub line: usize,
+	pub column: usize,
+}
+
+impl SourceCodeUnit {
+	/// Resolves a raw byte offset into a `LineColumn`, binary-searching
+	/// `line_offsets` for the greatest offset `<= raw_index`.
+	pub fn line_col(&self, raw_index: usize) -> LineColumn {
+		let line = match self.line_offsets.binary_search(&raw_index) {
+			Ok(line) => line,
+			Err(insertion_point) => insertion_point - 1,
+		};
+		let line_start = self.line_offsets[line];
+		let column = self.content[line_start..raw_index].chars().count() + 1;
+		LineColumn { line, column }
+	}
+}
+
+#[derive(Debug)]
+pub enum ParsingError {
+	EofInComment {loc: Loc},
+	UnexpectedCharacter {ch: char, loc: Loc},
+	MalformedNumericLiteral {loc: Loc},
+	UnexpectedToken {description: String, loc: Loc},
+}
+
+impl std::fmt::Display for ParsingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{} at line {}", self.message(), self.loc().line_start)
+	}
+}
+
+impl ParsingError {
+	fn loc(&self) -> &Loc {
+		match self {
+			ParsingError::EofInComment { loc } => loc,
+			ParsingError::UnexpectedCharacter { loc, .. } => loc,
+			ParsingError::MalformedNumericLiteral { loc } => loc,
+			ParsingError::UnexpectedToken { loc, .. } => loc,
+		}
+	}
+
+	/// This error's message, without any location reference, so that callers
+	/// can combine it with whichever location info (line only, or line and
+	/// column) fits their context.
+	fn message(&self) -> String {
+		match self {
+			ParsingError::EofInComment { .. } => "end-of-file in comment started".to_string(),
+			ParsingError::UnexpectedCharacter { ch, .. } => format!("unexpected character `{}`", ch),
+			ParsingError::MalformedNumericLiteral { .. } => "malformed numeric literal".to_string(),
+			ParsingError::UnexpectedToken { description, .. } => format!("unexpected {}", description),
+		}
+	}
+
+	/// Renders this error as the offending source line followed by a line of
+	/// carets underlining the exact span, `^^^^`-style. A span that spans
+	/// several lines is underlined from its start column to the end of the
+	/// first line only.
+	pub fn render_with_source(&self, colorize: bool) -> String {
+		let loc = self.loc();
+		let scu = &loc.scu;
+		let start = loc.line_col_start();
+		let end = loc.line_col_end();
+
+		let line_start_index = scu.line_offsets[start.line];
+		let line_end_index = scu
+			.line_offsets
+			.get(start.line + 1)
+			.map(|&offset| offset - 1)
+			.unwrap_or_else(|| scu.content.len());
+		let source_line = &scu.content[line_start_index..line_end_index];
+
+		let caret_count = if end.line == start.line {
+			(end.column - start.column).max(1)
+		} else {
+			source_line.chars().count() + 1 - start.column
+		};
+		let mut carets = " ".repeat(start.column - 1);
+		carets.push_str(&"^".repeat(caret_count));
+
+		let header = format!("{} at line {}, column {}", self.message(), start.line + 1, start.column);
+		if colorize {
+			format!(
+				"{}\n{}\n{}{}{}",
+				header, source_line, styles::BOLD_LIGHT_RED, carets, styles::NORMAL
+			)
+		} else {
+			format!("{}\n{}\n{}", header, source_line, carets)
+		}
+	}
+}
+
+
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct ReadingHead {
+	scu: Rc<SourceCodeUnit>,
+	raw_index: usize,
+	line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Loc {
+	scu: Rc<SourceCodeUnit>,
+	line_start: usize,
+	raw_index_start: usize,
+	raw_length: usize,
+}
+
+impl Loc {
+	/// The line and column of the first character of this span.
+	pub fn line_col_start(&self) -> LineColumn {
+		self.scu.line_col(self.raw_index_start)
+	}
+
+	/// The line and column just past the last character of this span.
+	pub fn line_col_end(&self) -> LineColumn {
+		self.scu.line_col(self.raw_index_start + self.raw_length)
+	}
+}
+
+/// A comment's text and `Loc` (delimiters included), paired with whether it
+/// appeared before any newline was crossed since the previous token — i.e.
+/// whether it trails that previous token on the same line, rather than
+/// leading whatever comes next. In source order.
+pub type CommentList = Vec<(String, Loc, bool)>;
+
+impl ReadingHead {
+	pub fn from_scu(scu: Rc<SourceCodeUnit>) -> ReadingHead {
+		ReadingHead {
+			scu: scu,
+			raw_index: 0,
+			line: 1,
+		}
+	}
+
+	fn peek_cur_char(&self) -> Option<char> {
+		self.scu.content[self.raw_index..].chars().next()
+	}
+
+	fn goto_next_char(&mut self) {
+		if let Some(ch) = self.peek_cur_char() {
+			self.raw_index += ch.len_utf8();
+			match ch {
+				'\n' => self.line += 1,
+				_ => (),
+			}
+		}
+	}
+
+	/// Skips forward to the next statement boundary (a newline, or EOF), so
+	/// that parsing can resume after a synthesized `Invalid` node instead of
+	/// stopping at the first syntax error.
+	pub fn skip_to_next_stmt_boundary(&mut self) {
+		while let Some(ch) = self.peek_cur_char() {
+			self.goto_next_char();
+			if ch == '\n' {
+				break;
+			}
+		}
+	}
+
+	fn cur_char_loc(&self) -> Loc {
+		Loc {
+			scu: Rc::clone(&self.scu),
+			line_start: self.line,
+			raw_index_start: self.raw_index,
+			raw_length: match self.peek_cur_char() {
+				Some(ch) => ch.len_utf8(),
+				None => 0,
+			},
+		}
+	}
+
+	/// Skips whitespace and `#...#` comments, returning the text, `Loc`
+	/// (delimiters included), and same-line flag of each comment encountered,
+	/// in source order.
+	fn skip_ws(&mut self) -> Result<CommentList, ParsingError> {
+		let mut comments: CommentList = Vec::new();
+		let mut comment: Option<(Loc, String)> = None;
+		let mut crossed_newline = false;
+		loop {
+			match self.peek_cur_char() {
+				Some('#') => match comment.take() {
+					Some((mut loc, text)) => {
+						loc.raw_length = self.raw_index + 1 - loc.raw_index_start;
+						comments.push((text, loc, !crossed_newline));
+					},
+					None => comment = Some((self.cur_char_loc(), String::new())),
+				},
+				Some(ch) if comment.is_some() => {
+					if let Some((_, text)) = comment.as_mut() {
+						text.push(ch);
+					}
+				},
+				Some('\n') => crossed_newline = true,
+				Some(ch) if !ch.is_ascii_whitespace() => break,
+				Some(_) => (),
+				None => match comment {
+					Some((loc, _)) => return Err(ParsingError::EofInComment { loc }),
+					None => break,
+				},
+			}
+			self.goto_next_char();
+		}
+		Ok(comments)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum Tok {
+	Word(String),
+	Integer(String),
+	Float(String),
+	BinOp(String),
+	Left(String),
+	Right(String),
+	Void,
+}
+
+impl Tok {
+	pub fn is_void(&self) -> bool {
+		match self {
+			Tok::Void => true,
+			_ => false,
+		}
+	}
+}
+
+impl ReadingHead {
+	/// Reads the next token, along with any `#...#` comments skipped over to
+	/// reach it. The caller (building the `ast::Node` this token belongs to)
+	/// is responsible for attaching those comments as leading trivia.
+	pub fn read_cur_tok(&mut self) -> Result<(Tok, Loc, CommentList), ParsingError> {
+		let comments = self.skip_ws()?;
+		match self.peek_cur_char() {
+			Some(ch) if ch.is_ascii_alphabetic() => {
+				let (word, loc) = self.read_cur_word();
+				Ok((Tok::Word(word), loc, comments))
+			},
+			Some(ch) if ch.is_ascii_digit() => {
+				let (tok, loc) = self.read_cur_number()?;
+				Ok((tok, loc, comments))
+			},
+			Some('<') if self.scu.content[self.raw_index + 1..].starts_with('-') => {
+				self.goto_next_char();
+				self.goto_next_char();
+				Ok((Tok::BinOp("<-".to_string()), self.cur_char_loc(), comments))
+			},
+			Some(ch) if ch == '+' || ch == '-' || ch == '*' || ch == '/' || ch == '>' => {
+				self.goto_next_char();
+				Ok((Tok::BinOp(ch.to_string()), self.cur_char_loc(), comments))
+			},
+			Some(ch) if ch == '(' || ch == '[' || ch == '{' => {
+				self.goto_next_char();
+				Ok((Tok::Left(ch.to_string()), self.cur_char_loc(), comments))
+			},
+			Some(ch) if ch == ')' || ch == ']' || ch == '}' => {
+				self.goto_next_char();
+				Ok((Tok::Right(ch.to_string()), self.cur_char_loc(), comments))
+			},
+			Some(ch) => Err(ParsingError::UnexpectedCharacter {
+				ch, loc: self.cur_char_loc(),
+			}),
+			None => Ok((Tok::Void, self.cur_char_loc(), comments)),
+		}
+	}
+
+	/// Like `read_cur_tok`, but never bails out: on an unexpected character
+	/// or malformed numeric literal, the error is pushed to `errors`, the
+	/// offending character (or literal) is skipped, and reading resumes from
+	/// there. This
+	/// is the token-level half of resilient parsing; the statement-level
+	/// recovery (synthesizing `Stmt::Invalid`/`Expr::Invalid` nodes and
+	/// resuming at the next statement boundary) belongs to the `parser2`
+	/// layer built on top of this one.
+	pub fn read_cur_tok_resilient(&mut self, errors: &mut Vec<ParsingError>) -> (Tok, Loc, CommentList) {
+		loop {
+			match self.read_cur_tok() {
+				Ok(tok_and_loc) => return tok_and_loc,
+				Err(err) => {
+					let can_advance = self.peek_cur_char().is_some();
+					// A malformed numeric literal has already been consumed by
+					// `read_cur_number`; only an unexpected character is still
+					// under the head.
+					let skip_offending = matches!(err, ParsingError::UnexpectedCharacter { .. });
+					errors.push(err);
+					if !can_advance {
+						return (Tok::Void, self.cur_char_loc(), Vec::new());
+					}
+					if skip_offending {
+						self.goto_next_char();
+					}
+				},
+			}
+		}
+	}
+
+	fn read_cur_word(&mut self) -> (String, Loc) {
+		let mut word_string = String::new();
+		let mut loc = self.cur_char_loc();
+		while let Some(ch) = self.peek_cur_char() {
+			if !ch.is_ascii_alphabetic() {
+				break;
+			}
+			word_string.push(ch);
+			self.goto_next_char();
+		}
+		std::assert!(word_string.len() >= 1);
+		loc.raw_length = word_string.bytes().len();
+		(word_string, loc)
+	}
+
+	/// Reads an integer or float literal: an optional `0x`/`0o`/`0b` radix
+	/// prefix (decimal only past this point), `_` digit separators (stripped
+	/// before parsing), and, for decimal literals, a fractional part and/or
+	/// an exponent. Rejects malformed forms like a bare `0x` or a trailing
+	/// `_` with `ParsingError::MalformedNumericLiteral`.
+	fn read_cur_number(&mut self) -> Result<(Tok, Loc), ParsingError> {
+		let mut loc = self.cur_char_loc();
+
+		let radix: u32 = if self.peek_cur_char() == Some('0') {
+			match self.scu.content[self.raw_index + 1..].chars().next() {
+				Some('x') | Some('X') => 16,
+				Some('o') | Some('O') => 8,
+				Some('b') | Some('B') => 2,
+				_ => 10,
+			}
+		} else {
+			10
+		};
+		let has_prefix = radix != 10;
+		if has_prefix {
+			self.goto_next_char();
+			self.goto_next_char();
+		}
+
+		let mut digits = String::new();
+		let mut trailing_separator = false;
+		self.read_cur_digit_run(radix, &mut digits, &mut trailing_separator);
+
+		let mut is_float = false;
+		if !has_prefix
+			&& self.peek_cur_char() == Some('.')
+			&& matches!(self.scu.content[self.raw_index + 1..].chars().next(), Some(ch) if ch.is_ascii_digit())
+		{
+			is_float = true;
+			digits.push('.');
+			self.goto_next_char();
+			self.read_cur_digit_run(10, &mut digits, &mut trailing_separator);
+		}
+		if !has_prefix && matches!(self.peek_cur_char(), Some('e') | Some('E')) {
+			let after_e_index = self.raw_index + 1; // 'e'/'E' are one byte long
+			let mut rest = self.scu.content[after_e_index..].chars();
+			let (sign, digits_index) = match rest.next() {
+				Some(sign_ch) if sign_ch == '+' || sign_ch == '-' => {
+					(Some(sign_ch), after_e_index + sign_ch.len_utf8())
+				},
+				_ => (None, after_e_index),
+			};
+			if matches!(self.scu.content[digits_index..].chars().next(), Some(d) if d.is_ascii_digit()) {
+				is_float = true;
+				digits.push('e');
+				self.goto_next_char();
+				if let Some(sign_ch) = sign {
+					digits.push(sign_ch);
+					self.goto_next_char();
+				}
+				self.read_cur_digit_run(10, &mut digits, &mut trailing_separator);
+			}
+		}
+
+		loc.raw_length = self.raw_index - loc.raw_index_start;
+
+		if digits.is_empty() || trailing_separator {
+			return Err(ParsingError::MalformedNumericLiteral { loc });
+		}
+
+		if is_float {
+			Ok((Tok::Float(digits), loc))
+		} else if has_prefix {
+			let prefix = match radix {
+				16 => "0x",
+				8 => "0o",
+				2 => "0b",
+				_ => unreachable!(),
+			};
+			Ok((Tok::Integer(format!("{}{}", prefix, digits)), loc))
+		} else {
+			Ok((Tok::Integer(digits), loc))
+		}
+	}
+
+	/// Consumes a run of `radix`-digits and `_` separators, appending digits
+	/// (not separators) to `digits`. Sets `trailing_separator` to whether the
+	/// run's last consumed character was a separator.
+	fn read_cur_digit_run(&mut self, radix: u32, digits: &mut String, trailing_separator: &mut bool) {
+		while let Some(ch) = self.peek_cur_char() {
+			if ch == '_' {
+				*trailing_separator = true;
+				self.goto_next_char();
+				continue;
+			}
+			if !ch.is_digit(radix) {
+				break;
+			}
+			digits.push(ch);
+			*trailing_separator = false;
+			self.goto_next_char();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lex_one(src: &str) -> Tok {
+		let scu = Rc::new(SourceCodeUnit::from_str(src, "test".to_string()));
+		let mut head = ReadingHead::from_scu(scu);
+		let (tok, _loc, _comments) = head.read_cur_tok().expect("expected a valid token");
+		tok
+	}
+
+	#[test]
+	fn lexes_radix_prefixed_integers() {
+		assert!(matches!(lex_one("0xFF"), Tok::Integer(s) if s == "0xFF"));
+		assert!(matches!(lex_one("0o17"), Tok::Integer(s) if s == "0o17"));
+		assert!(matches!(lex_one("0b1010"), Tok::Integer(s) if s == "0b1010"));
+	}
+
+	#[test]
+	fn lexes_digit_separators() {
+		assert!(matches!(lex_one("1_000"), Tok::Integer(s) if s == "1000"));
+	}
+
+	#[test]
+	fn lexes_float_with_exponent() {
+		assert!(matches!(lex_one("3.14e-2"), Tok::Float(s) if s == "3.14e-2"));
+	}
+
+	#[test]
+	fn rejects_malformed_numeric_literal() {
+		let scu = Rc::new(SourceCodeUnit::from_str("0x", "test".to_string()));
+		let mut head = ReadingHead::from_scu(scu);
+		assert!(matches!(
+			head.read_cur_tok(),
+			Err(ParsingError::MalformedNumericLiteral { .. })
+		));
+	}
+
+	#[test]
+	fn rendered_diagnostic_states_the_line_only_once() {
+		let scu = Rc::new(SourceCodeUnit::from_str("@", "test".to_string()));
+		let mut head = ReadingHead::from_scu(scu);
+		let err = head.read_cur_tok().unwrap_err();
+		let rendered = err.render_with_source(false);
+		let header = rendered.lines().next().unwrap();
+		assert_eq!(header.matches("at line").count(), 1);
+	}
+}